@@ -0,0 +1,99 @@
+/// Which of a window's endpoints are considered part of the window.
+///
+/// This matters whenever a timestamp lands exactly on a boundary shared by two adjacent windows:
+/// depending on the mode, such a point belongs to the earlier window, the later one, both, or
+/// neither.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClosedWindow {
+    Left,
+    Right,
+    Both,
+    None,
+}
+
+/// The boundaries of a single window or of the whole timestamp range being grouped.
+///
+/// Whether `start` and `stop` themselves are members of the bounds is determined by the
+/// [`ClosedWindow`] passed to [`Bounds::is_member`].
+#[derive(Clone, Copy, Debug)]
+pub struct Bounds {
+    pub(crate) start: i64,
+    pub(crate) stop: i64,
+}
+
+impl Bounds {
+    pub fn new(start: i64, stop: i64) -> Self {
+        Bounds { start, stop }
+    }
+
+    /// the bounds that span the full (assumed sorted) `time` slice, or `None` if it's empty
+    pub(crate) fn from_time(time: &[i64]) -> Option<Self> {
+        let start = *time.first()?;
+        let stop = time[time.len() - 1].saturating_add(1);
+        Some(Bounds::new(start, stop))
+    }
+
+    /// true if `t` is at or past the first point that can belong to these bounds under `closed`
+    ///
+    /// For a sorted slice this is monotonic in `t` (once true, stays true), so it can be used
+    /// directly as a `partition_point` predicate to binary-search for a window's starting index.
+    pub(crate) fn is_at_or_past_start(&self, t: i64, closed: ClosedWindow) -> bool {
+        match closed {
+            ClosedWindow::Left | ClosedWindow::Both => t >= self.start,
+            ClosedWindow::Right | ClosedWindow::None => t > self.start,
+        }
+    }
+
+    /// true if `t` is past the last point that can belong to these bounds under `closed`
+    ///
+    /// Monotonic in the same sense as [`Bounds::is_at_or_past_start`], so it doubles as a
+    /// `partition_point` predicate for a window's end index.
+    pub(crate) fn is_past_end(&self, t: i64, closed: ClosedWindow) -> bool {
+        match closed {
+            ClosedWindow::Right | ClosedWindow::Both => t > self.stop,
+            ClosedWindow::Left | ClosedWindow::None => t >= self.stop,
+        }
+    }
+
+    /// true if `t` falls within these bounds under the given closed-interval mode
+    pub fn is_member(&self, t: i64, closed: ClosedWindow) -> bool {
+        self.is_at_or_past_start(t, closed) && !self.is_past_end(t, closed)
+    }
+
+    pub fn duration(&self) -> i64 {
+        self.stop - self.start
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_is_member_closed_modes() {
+        let bounds = Bounds::new(10, 20);
+
+        assert!(bounds.is_member(10, ClosedWindow::Left));
+        assert!(!bounds.is_member(20, ClosedWindow::Left));
+
+        assert!(!bounds.is_member(10, ClosedWindow::Right));
+        assert!(bounds.is_member(20, ClosedWindow::Right));
+
+        assert!(bounds.is_member(10, ClosedWindow::Both));
+        assert!(bounds.is_member(20, ClosedWindow::Both));
+
+        assert!(!bounds.is_member(10, ClosedWindow::None));
+        assert!(!bounds.is_member(20, ClosedWindow::None));
+
+        for closed in [
+            ClosedWindow::Left,
+            ClosedWindow::Right,
+            ClosedWindow::Both,
+            ClosedWindow::None,
+        ] {
+            assert!(bounds.is_member(15, closed));
+            assert!(!bounds.is_member(5, closed));
+            assert!(!bounds.is_member(25, closed));
+        }
+    }
+}
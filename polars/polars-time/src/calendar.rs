@@ -0,0 +1,126 @@
+use crate::unit::TimeUnit;
+use chrono::{Duration as ChronoDuration, LocalResult, NaiveDateTime, TimeZone, Timelike};
+use chrono_tz::Tz;
+
+pub(crate) const NS_IN_SECOND: i64 = 1_000_000_000;
+
+/// Interpret `v` as a number of nanoseconds since the epoch and turn it into a naive (UTC)
+/// datetime.
+pub fn timestamp_ns_to_datetime(v: i64) -> NaiveDateTime {
+    let secs = v.div_euclid(NS_IN_SECOND);
+    let nsec = v.rem_euclid(NS_IN_SECOND);
+    NaiveDateTime::from_timestamp(secs, nsec as u32)
+}
+
+/// Turn a naive (UTC) datetime back into a number of nanoseconds since the epoch.
+pub fn datetime_to_timestamp_ns(v: NaiveDateTime) -> i64 {
+    v.timestamp() * NS_IN_SECOND + v.timestamp_subsec_nanos() as i64
+}
+
+/// Convert a timestamp expressed in `tu` to nanoseconds, saturating instead of overflowing when
+/// `t` is so far in the future/past that the equivalent nanosecond count doesn't fit in an i64
+/// (this is only possible for `tu != Nanoseconds`, since a nanosecond column is already at its
+/// native resolution).
+pub(crate) fn timestamp_to_ns(t: i64, tu: TimeUnit) -> i64 {
+    match t.checked_mul(tu.ns_per_unit()) {
+        Some(ns) => ns,
+        None if t > 0 => i64::MAX,
+        None => i64::MIN,
+    }
+}
+
+/// Convert a nanosecond timestamp back down to `tu`. Always safe: going from nanoseconds to a
+/// coarser (or equal) unit only shrinks the magnitude.
+pub(crate) fn ns_to_timestamp(ns: i64, tu: TimeUnit) -> i64 {
+    ns / tu.ns_per_unit()
+}
+
+/// Interpret `v` (nanoseconds since the epoch, UTC) as wall-clock time in `tz`.
+pub(crate) fn timestamp_ns_to_local_datetime(v: i64, tz: Tz) -> NaiveDateTime {
+    tz.timestamp_nanos(v).naive_local()
+}
+
+/// Resolve a local (wall-clock) datetime in `tz` to a single UTC instant in nanoseconds,
+/// handling the two ways a local time can fail to map 1:1 onto UTC across a DST transition:
+///
+/// - during a spring-forward gap the local time never occurs, so the next valid instant is used
+/// - during a fall-back overlap the local time occurs twice, so the earlier instant is used
+pub(crate) fn local_to_utc_ns(local: NaiveDateTime, tz: Tz) -> i64 {
+    match tz.from_local_datetime(&local) {
+        LocalResult::Single(dt) => datetime_to_timestamp_ns(dt.naive_utc()),
+        LocalResult::Ambiguous(earlier, _later) => datetime_to_timestamp_ns(earlier.naive_utc()),
+        LocalResult::None => {
+            // step from the start of the minute, so the first valid minute boundary after the
+            // gap is found rather than the same second-of-minute past it
+            let mut candidate = local
+                .with_second(0)
+                .and_then(|dt| dt.with_nanosecond(0))
+                .unwrap();
+            loop {
+                candidate += ChronoDuration::minutes(1);
+                if let LocalResult::Single(dt) = tz.from_local_datetime(&candidate) {
+                    break datetime_to_timestamp_ns(dt.naive_utc());
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn is_leap_year(year: i32) -> bool {
+    (year % 4 == 0 && year % 100 != 0) || year % 400 == 0
+}
+
+/// The number of days in `month` (1-indexed) of `year`.
+pub(crate) fn last_day_of_month(month: u32, year: i32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 => {
+            if is_leap_year(year) {
+                29
+            } else {
+                28
+            }
+        }
+        _ => unreachable!("month must be in 1..=12"),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use chrono::{NaiveDate, NaiveTime};
+
+    #[test]
+    fn test_local_to_utc_spring_forward_gap() {
+        // 2021-03-14 02:30:00 never happens in America/New_York (clocks jump 02:00 -> 03:00)
+        let tz: Tz = "America/New_York".parse().unwrap();
+        let local = NaiveDateTime::new(NaiveDate::from_ymd(2021, 3, 14), NaiveTime::from_hms(2, 30, 0));
+        let resolved = timestamp_ns_to_local_datetime(local_to_utc_ns(local, tz), tz);
+        assert!(resolved >= NaiveDateTime::new(NaiveDate::from_ymd(2021, 3, 14), NaiveTime::from_hms(3, 0, 0)));
+    }
+
+    #[test]
+    fn test_local_to_utc_fall_back_overlap() {
+        // 2021-11-07 01:30:00 happens twice in America/New_York; we should pick the earlier
+        // (EDT, UTC-4) of the two instants rather than the later one (EST, UTC-5)
+        let tz: Tz = "America/New_York".parse().unwrap();
+        let local = NaiveDateTime::new(NaiveDate::from_ymd(2021, 11, 7), NaiveTime::from_hms(1, 30, 0));
+        let edt_instant = datetime_to_timestamp_ns(local) + 4 * 60 * 60 * NS_IN_SECOND;
+        assert_eq!(local_to_utc_ns(local, tz), edt_instant);
+    }
+
+    #[test]
+    fn test_last_day_of_month() {
+        assert_eq!(last_day_of_month(2, 2000), 29);
+        assert_eq!(last_day_of_month(2, 2001), 28);
+        assert_eq!(last_day_of_month(4, 2001), 30);
+    }
+
+    #[test]
+    fn test_roundtrip_ns_datetime() {
+        let ns = 1_000_000_000_123_456_789;
+        let dt = timestamp_ns_to_datetime(ns);
+        assert_eq!(datetime_to_timestamp_ns(dt), ns);
+    }
+}
@@ -0,0 +1,336 @@
+use crate::calendar::{
+    datetime_to_timestamp_ns, last_day_of_month, local_to_utc_ns, ns_to_timestamp,
+    timestamp_ns_to_datetime, timestamp_ns_to_local_datetime, timestamp_to_ns, NS_IN_SECOND,
+};
+use crate::unit::TimeUnit;
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime};
+use chrono_tz::Tz;
+
+/// The length of a window, either a fixed number of nanoseconds, a number of calendar months, or
+/// both (e.g. "1 month plus 2 days").
+///
+/// Calendar months have a variable length, so a `Duration` that carries a `months` component
+/// can only be truncated/added relative to a concrete timestamp, unlike a pure nanosecond
+/// duration which has a constant length.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Duration {
+    /// the number of calendar months in this duration, zero if there is none
+    months: i64,
+    /// the number of nanoseconds in this duration, zero if there is none
+    nsecs: i64,
+}
+
+/// Convert `ns` (i128 nanoseconds, wide enough to hold any `i64` timestamp in any [`TimeUnit`]
+/// without overflowing) down to `tu`, saturating against `tu`'s own `i64::MAX`/`MIN` if it's out
+/// of range.
+///
+/// Saturating in ns first and only then dividing back down to `tu` would saturate to ns's range
+/// instead of `tu`'s — e.g. nanosecond-`i64::MAX` divided back down to milliseconds is nowhere
+/// near millisecond-`i64::MAX`. Doing the division in the wider i128 and saturating only once,
+/// at the end, avoids that.
+///
+/// The division is a floor (`div_euclid`), not a truncation: `ns` need not be an exact multiple
+/// of `tu.ns_per_unit()` (e.g. a fixed `nsecs` that isn't a multiple of it, already floored to its
+/// own grid in ns), and truncating such a value toward zero would round a negative boundary back
+/// up instead of down.
+fn saturating_ns_to_tu(ns: i128, tu: TimeUnit) -> i64 {
+    ns.div_euclid(tu.ns_per_unit() as i128)
+        .clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
+
+/// The widest range of years whose nanosecond-since-epoch representation is guaranteed not to
+/// overflow an i64 (`i64::MAX` ns is only about 292 years past 1970), with a 1-year margin so a
+/// date near the boundary plus a day-of-month/time-of-day component still converts safely.
+const MIN_DATE_YEAR: i32 = 1970 - 291;
+const MAX_DATE_YEAR: i32 = 1970 + 291;
+
+impl Duration {
+    pub fn new(months: i64, nsecs: i64) -> Self {
+        Duration { months, nsecs }
+    }
+
+    pub fn from_nsecs(v: i64) -> Self {
+        Duration::new(0, v)
+    }
+
+    pub fn from_seconds(v: i64) -> Self {
+        Duration::from_nsecs(v * NS_IN_SECOND)
+    }
+
+    pub fn from_minutes(v: i64) -> Self {
+        Duration::from_seconds(v * 60)
+    }
+
+    pub fn from_days(v: i64) -> Self {
+        Duration::from_seconds(v * 60 * 60 * 24)
+    }
+
+    pub fn from_months(v: i64) -> Self {
+        Duration::new(v, 0)
+    }
+
+    pub fn months(&self) -> i64 {
+        self.months
+    }
+
+    pub fn nsecs(&self) -> i64 {
+        self.nsecs
+    }
+
+    /// true if this duration has no calendar (variable-length) component
+    pub fn is_fixed_duration(&self) -> bool {
+        self.months == 0
+    }
+
+    /// the length of this duration expressed in `tu`, only meaningful when
+    /// [`is_fixed_duration`] holds, since a calendar duration has no fixed length
+    ///
+    /// [`is_fixed_duration`]: Duration::is_fixed_duration
+    pub fn duration(&self, tu: TimeUnit) -> i64 {
+        self.nsecs / tu.ns_per_unit()
+    }
+
+    /// A best-effort average length of this duration in `tu`, assuming 30 days per calendar
+    /// month. Only meant to size a `Vec::with_capacity` hint — unlike [`Duration::duration`],
+    /// this is not zero for a duration with a calendar component. A fixed part shorter than one
+    /// unit of `tu` still rounds down to zero here.
+    pub(crate) fn estimated_duration(&self, tu: TimeUnit) -> i64 {
+        const AVG_DAYS_PER_MONTH: i64 = 30;
+        let months_ns = self
+            .months
+            .saturating_mul(AVG_DAYS_PER_MONTH)
+            .saturating_mul(24)
+            .saturating_mul(60)
+            .saturating_mul(60)
+            .saturating_mul(NS_IN_SECOND);
+        months_ns.saturating_add(self.nsecs) / tu.ns_per_unit()
+    }
+
+    /// true if this duration has no calendar component and its fixed part rounds down to zero
+    /// in `tu` — i.e. [`Duration::truncate`]/[`Duration::add`] would not advance `t` at all
+    pub(crate) fn is_zero_in(&self, tu: TimeUnit) -> bool {
+        self.months == 0 && self.duration(tu) == 0
+    }
+
+    /// This duration repeated `n` times.
+    ///
+    /// Used by `BoundsIter` to locate the nth window with a single [`Duration::add`] from the
+    /// iterator's origin instead of `n` successive additions: since a fixed `nsecs` that isn't an
+    /// exact multiple of `tu.ns_per_unit()` rounds on every call, adding it `n` times in a row
+    /// would compound that rounding into visible drift, whereas scaling first and adding once
+    /// only rounds at the very end.
+    pub(crate) fn scaled(&self, n: i64) -> Duration {
+        Duration::new(self.months.saturating_mul(n), self.nsecs.saturating_mul(n))
+    }
+
+    /// advance `year`/`month` by this duration's `months` component, normalizing the year and
+    /// clamping `day` to the last valid day of the resulting month
+    ///
+    /// Saturates rather than overflows if `self.months` is large enough to push `total_months`
+    /// out of `i64` range, matching every other arithmetic path in this module; the resulting
+    /// year is further clamped to [`MIN_DATE_YEAR`]/[`MAX_DATE_YEAR`] so the caller can always
+    /// build a valid `NaiveDate` from it and convert that back to nanoseconds without overflowing.
+    fn step_months(&self, year: i32, month: u32, day: u32) -> (i32, u32, u32) {
+        let total_months = (year as i64)
+            .saturating_mul(12)
+            .saturating_add(month as i64 - 1)
+            .saturating_add(self.months);
+        let year = total_months
+            .div_euclid(12)
+            .clamp(MIN_DATE_YEAR as i64, MAX_DATE_YEAR as i64) as i32;
+        let month = (total_months.rem_euclid(12) + 1) as u32;
+        let day = day.min(last_day_of_month(month, year));
+        (year, month, day)
+    }
+
+    /// Floor `t` (a timestamp in `tu`) to the nearest boundary of this duration.
+    ///
+    /// For a fixed duration this rounds down to a multiple of the duration's length. For a
+    /// calendar duration this zeroes out everything below month granularity: the day is set to
+    /// 1 and the time to midnight.
+    pub fn truncate(&self, t: i64, tu: TimeUnit) -> i64 {
+        if self.months != 0 {
+            let ts = timestamp_ns_to_datetime(timestamp_to_ns(t, tu));
+            let floored = NaiveDateTime::new(
+                NaiveDate::from_ymd(ts.year(), ts.month(), 1),
+                NaiveTime::from_hms(0, 0, 0),
+            );
+            ns_to_timestamp(datetime_to_timestamp_ns(floored), tu)
+        } else if self.nsecs == 0 {
+            t
+        } else {
+            // reason about the floor in i128 nanoseconds, wide enough that converting `t` up to
+            // ns never overflows even when `t` is close to `tu`'s own i64::MAX/MIN, then saturate
+            // back down to `tu`'s own range in one step at the end (see `saturating_ns_to_tu`)
+            let t_ns = t as i128 * tu.ns_per_unit() as i128;
+            let floored_ns = t_ns - t_ns.rem_euclid(self.nsecs as i128);
+            saturating_ns_to_tu(floored_ns, tu)
+        }
+    }
+
+    /// Add this duration to the timestamp `t` (in `tu`), saturating instead of overflowing if `t`
+    /// is close enough to `i64::MAX`/`MIN` that the fixed component would push it out of range.
+    ///
+    /// The calendar component, if any, is applied first by advancing the month/year fields,
+    /// after which the fixed component is added.
+    pub fn add(&self, t: i64, tu: TimeUnit) -> i64 {
+        let t = if self.months != 0 {
+            let ts = timestamp_ns_to_datetime(timestamp_to_ns(t, tu));
+            let (year, month, day) = self.step_months(ts.year(), ts.month(), ts.day());
+            let stepped = NaiveDateTime::new(NaiveDate::from_ymd(year, month, day), ts.time());
+            ns_to_timestamp(datetime_to_timestamp_ns(stepped), tu)
+        } else {
+            t
+        };
+        // as in `truncate`, reason about the fixed part in i128 nanoseconds so it can't overflow
+        // partway through; see `saturating_ns_to_tu`
+        let t_ns = t as i128 * tu.ns_per_unit() as i128 + self.nsecs as i128;
+        saturating_ns_to_tu(t_ns, tu)
+    }
+
+    /// Like [`Duration::truncate`], but the boundary is computed against local wall-clock time
+    /// in `tz` instead of UTC, so e.g. a daily window aligns to local midnight rather than UTC
+    /// midnight.
+    pub fn truncate_tz(&self, t: i64, tz: Tz, tu: TimeUnit) -> i64 {
+        let local = timestamp_ns_to_local_datetime(timestamp_to_ns(t, tu), tz);
+        let floored_local = if self.months != 0 {
+            NaiveDateTime::new(
+                NaiveDate::from_ymd(local.year(), local.month(), 1),
+                NaiveTime::from_hms(0, 0, 0),
+            )
+        } else if self.nsecs == 0 {
+            local
+        } else {
+            // `local` is always reasoned about at full nanosecond resolution, regardless of `tu`
+            let local_ns = datetime_to_timestamp_ns(local);
+            timestamp_ns_to_datetime(local_ns.saturating_sub(local_ns.rem_euclid(self.nsecs)))
+        };
+        ns_to_timestamp(local_to_utc_ns(floored_local, tz), tu)
+    }
+
+    /// Like [`Duration::add`], but calendar and wall-clock components are advanced against local
+    /// time in `tz` before converting back to a UTC instant, so a window step that crosses a DST
+    /// transition still spans a full local day/hour rather than a fixed number of elapsed
+    /// seconds.
+    pub fn add_tz(&self, t: i64, tz: Tz, tu: TimeUnit) -> i64 {
+        let local = timestamp_ns_to_local_datetime(timestamp_to_ns(t, tu), tz);
+        let stepped_local = if self.months != 0 {
+            let (year, month, day) = self.step_months(local.year(), local.month(), local.day());
+            NaiveDateTime::new(NaiveDate::from_ymd(year, month, day), local.time())
+        } else {
+            local
+        };
+        // `local` is always reasoned about at full nanosecond resolution, regardless of `tu`
+        let local_ns = datetime_to_timestamp_ns(stepped_local).saturating_add(self.nsecs);
+        ns_to_timestamp(local_to_utc_ns(timestamp_ns_to_datetime(local_ns), tz), tu)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_truncate_fixed() {
+        let d = Duration::from_seconds(30);
+        assert_eq!(d.truncate(45_000_000_000, TimeUnit::Nanoseconds), 30_000_000_000);
+    }
+
+    #[test]
+    fn test_truncate_fixed_milliseconds() {
+        // the same 30 second period should truncate identically regardless of the column's unit
+        let d = Duration::from_seconds(30);
+        assert_eq!(d.truncate(45_000, TimeUnit::Milliseconds), 30_000);
+    }
+
+    #[test]
+    fn test_add_months() {
+        let d = Duration::from_months(1);
+        // 2001-01-31 + 1 month -> 2001-02-28 (clamped, not leap year)
+        let start =
+            datetime_to_timestamp_ns(NaiveDateTime::new(
+                NaiveDate::from_ymd(2001, 1, 31),
+                NaiveTime::from_hms(0, 0, 0),
+            ));
+        let expected = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 2, 28),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        assert_eq!(d.add(start, TimeUnit::Nanoseconds), expected);
+    }
+
+    #[test]
+    fn test_add_months_and_nsecs() {
+        // "1 month plus 2 days" should step the calendar part first, then add the ns part.
+        let d = Duration::new(1, Duration::from_days(2).nsecs());
+        let start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 1, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let expected = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 2, 3),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        assert_eq!(d.add(start, TimeUnit::Nanoseconds), expected);
+    }
+
+    #[test]
+    fn test_add_saturates_near_i64_max() {
+        // a far-future timestamp plus a fixed duration should saturate rather than panic
+        let d = Duration::from_seconds(30);
+        assert_eq!(d.add(i64::MAX - 5, TimeUnit::Milliseconds), i64::MAX);
+    }
+
+    #[test]
+    fn test_truncate_saturates_near_i64_min() {
+        let d = Duration::from_seconds(30);
+        assert_eq!(d.truncate(i64::MIN + 5, TimeUnit::Milliseconds), i64::MIN);
+    }
+
+    #[test]
+    fn test_add_months_saturates_near_i64_max() {
+        // an absurdly large `months` component should saturate the resulting year rather than
+        // overflow `total_months` in `step_months`
+        let d = Duration::from_months(i64::MAX);
+        let start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 1, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let result = d.add(start, TimeUnit::Nanoseconds);
+        assert_eq!(timestamp_ns_to_datetime(result).year(), MAX_DATE_YEAR);
+    }
+
+    #[test]
+    fn test_add_months_saturates_near_i64_min() {
+        let d = Duration::from_months(i64::MIN);
+        let start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 1, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let result = d.add(start, TimeUnit::Nanoseconds);
+        assert_eq!(timestamp_ns_to_datetime(result).year(), MIN_DATE_YEAR);
+    }
+
+    #[test]
+    fn test_truncate_floors_non_exact_negative_boundary() {
+        // -4us is -4000ns, which isn't a multiple of the 1500ns duration; the true floor is
+        // -4500ns, i.e. -5us, not -4us (truncating -4.5 toward zero would wrongly give -4)
+        let d = Duration::from_nsecs(1_500);
+        assert_eq!(d.truncate(-4, TimeUnit::Microseconds), -5);
+    }
+
+    #[test]
+    fn test_scaled_add_avoids_compounding_remainder() {
+        // a 1.5us duration doesn't divide evenly into microsecond resolution, so adding it 1000
+        // times in a row would round away 0.5us on every step and drift down to 1000us; scaling
+        // by 1000 and adding once rounds only at the end, landing on the true 1500us
+        let d = Duration::from_nsecs(1_500);
+        assert_eq!(d.scaled(1_000).add(0, TimeUnit::Microseconds), 1_500);
+
+        let mut drifted = 0i64;
+        for _ in 0..1_000 {
+            drifted = d.add(drifted, TimeUnit::Microseconds);
+        }
+        assert_eq!(drifted, 1_000);
+    }
+}
@@ -1,55 +1,84 @@
-use crate::bounds::Bounds;
-use crate::calendar::timestamp_ns_to_datetime;
-use crate::duration::Duration;
-use crate::window::Window;
+use crate::bounds::{Bounds, ClosedWindow};
+use crate::unit::TimeUnit;
+use crate::window::{Window, WindowError};
 
 pub type GroupTuples = Vec<(u32, Vec<u32>)>;
 
-pub fn groupby(window: Window, time: &[i64]) -> GroupTuples {
-    let mut boundary = Bounds::from(time);
-
-    let mut group_tuples = Vec::with_capacity(window.estimate_overlapping_bounds(boundary));
-    let mut latest_start = 0;
-
-    for bi in window.get_overlapping_bounds_iter(boundary) {
-        let mut group = vec![];
+/// Find the index of the first and one-past-the-last member of `bi` within `time`.
+///
+/// `time` is assumed sorted, so both endpoints can be located with a binary search against
+/// `Bounds::is_at_or_past_start`/`Bounds::is_past_end` — the same per-mode predicates
+/// `Bounds::is_member` is built from — instead of a linear scan, turning the per-window cost
+/// from O(n) into O(log n).
+fn window_members_range(time: &[i64], bi: &Bounds, closed_window: ClosedWindow) -> (usize, usize) {
+    let start_idx = time.partition_point(|&t| !bi.is_at_or_past_start(t, closed_window));
+    let end_idx = time.partition_point(|&t| !bi.is_past_end(t, closed_window));
+    (start_idx, end_idx.max(start_idx))
+}
 
-        // find starting point of window
-        loop {
-            latest_start += 1;
+/// Group the (assumed sorted) `time` slice by the windows of `window`, returning for each window
+/// the row index it starts at and the indices of all its members (sharing a starting index with
+/// an earlier window is fine: windows may overlap).
+///
+/// `tu` is the unit `time`'s values are stored in; the same `window` produces identical groupings
+/// regardless of whether `time` happens to be in nanoseconds, microseconds, or milliseconds.
+///
+/// Returns [`WindowError::EveryRoundsToZero`] if `window.every` rounds to zero in `tu`.
+///
+/// Returns an empty `GroupTuples` without error if `time` is empty — there are no windows to
+/// place members into.
+pub fn groupby(
+    window: Window,
+    time: &[i64],
+    closed_window: ClosedWindow,
+    tu: TimeUnit,
+) -> Result<GroupTuples, WindowError> {
+    let boundary = match Bounds::from_time(time) {
+        Some(boundary) => boundary,
+        None => return Ok(vec![]),
+    };
 
-            match time.get(latest_start - 1) {
-                Some(ts) => {
-                    if bi.is_member(*ts) {
-                        break;
-                    }
-                }
-                None => break,
-            }
-        }
+    let mut group_tuples = Vec::with_capacity(window.estimate_overlapping_bounds(boundary, tu));
 
-        // subtract 1 because the next window could also start from the same point
-        latest_start = latest_start.saturating_sub(1);
-
-        // find members of this window
-        let mut i = latest_start;
-        loop {
-            group.push(i as u32);
-            if i >= time.len() || !bi.is_member(time[i]) {
-                break;
-            }
-            i += 1
-        }
-        if !group.is_empty() {
-            group_tuples.push((group[0], group))
+    for bi in window.get_overlapping_bounds_iter(boundary, tu)? {
+        let (start_idx, end_idx) = window_members_range(time, &bi, closed_window);
+        if end_idx > start_idx {
+            let group = (start_idx as u32..end_idx as u32).collect::<Vec<_>>();
+            group_tuples.push((group[0], group));
         }
     }
-    group_tuples
+    Ok(group_tuples)
+}
+
+/// Like [`groupby`], but `time` need not be sorted.
+///
+/// `time` is argsorted internally and the window logic runs against that sorted order; the
+/// indices in the returned [`GroupTuples`] are then remapped back to positions in the original,
+/// untouched `time` slice, so callers can gather directly from it without sorting themselves.
+pub fn groupby_unsorted(
+    window: Window,
+    time: &[i64],
+    closed_window: ClosedWindow,
+    tu: TimeUnit,
+) -> Result<GroupTuples, WindowError> {
+    let mut sorted_idx = (0..time.len() as u32).collect::<Vec<_>>();
+    sorted_idx.sort_unstable_by_key(|&i| time[i as usize]);
+    let sorted_time = sorted_idx.iter().map(|&i| time[i as usize]).collect::<Vec<_>>();
+
+    let group_tuples = groupby(window, &sorted_time, closed_window, tu)?
+        .into_iter()
+        .map(|(start, group)| {
+            let group = group.into_iter().map(|i| sorted_idx[i as usize]).collect::<Vec<_>>();
+            (sorted_idx[start as usize], group)
+        })
+        .collect();
+    Ok(group_tuples)
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::duration::Duration;
     use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
 
     #[test]
@@ -91,9 +120,112 @@ mod test {
             Duration::from_seconds(30),
             Duration::from_seconds(0),
         );
-        let gt = groupby(window, &ts).into_iter().map(|g| g.1).collect::<Vec<_>>();
 
-        let expected = &[[0, 1, 2], [2, 3, 4], [4, 5, 6]];
+        // with both endpoints closed, a point exactly on a shared boundary (e.g. index 2 at
+        // 1:00:30) is a member of both of the adjacent windows
+        let gt = groupby(window, &ts, ClosedWindow::Both, TimeUnit::Nanoseconds)
+            .unwrap()
+            .into_iter()
+            .map(|g| g.1)
+            .collect::<Vec<_>>();
+        let expected = &[vec![0, 1, 2], vec![2, 3, 4], vec![4, 5, 6], vec![6]];
+        assert_eq!(gt, expected);
+
+        // with only the left endpoint closed, that same boundary point belongs to exactly one
+        // window: the one it starts
+        let gt = groupby(window, &ts, ClosedWindow::Left, TimeUnit::Nanoseconds)
+            .unwrap()
+            .into_iter()
+            .map(|g| g.1)
+            .collect::<Vec<_>>();
+        let expected = &[vec![0, 1], vec![2, 3], vec![4, 5], vec![6]];
+        assert_eq!(gt, expected);
+    }
+
+    #[test]
+    fn test_group_tuples_milliseconds() {
+        // the same grouping should come out whether the column is stored in ns or ms
+        let dt = &[
+            NaiveDateTime::new(NaiveDate::from_ymd(2001, 1, 1), NaiveTime::from_hms(1, 0, 0)),
+            NaiveDateTime::new(NaiveDate::from_ymd(2001, 1, 1), NaiveTime::from_hms(1, 0, 15)),
+            NaiveDateTime::new(NaiveDate::from_ymd(2001, 1, 1), NaiveTime::from_hms(1, 0, 45)),
+        ];
+        let ts_ms = dt
+            .iter()
+            .map(|dt| dt.timestamp_nanos() / 1_000_000)
+            .collect::<Vec<_>>();
+        let window = Window::new(
+            Duration::from_seconds(30),
+            Duration::from_seconds(30),
+            Duration::from_seconds(0),
+        );
+
+        let gt = groupby(window, &ts_ms, ClosedWindow::Left, TimeUnit::Milliseconds)
+            .unwrap()
+            .into_iter()
+            .map(|g| g.1)
+            .collect::<Vec<_>>();
+        let expected = &[vec![0, 1], vec![2]];
+        assert_eq!(gt, expected);
+    }
+
+    #[test]
+    fn test_groupby_unsorted() {
+        let dt = &[
+            NaiveDateTime::new(NaiveDate::from_ymd(2001, 1, 1), NaiveTime::from_hms(1, 0, 0)),
+            NaiveDateTime::new(NaiveDate::from_ymd(2001, 1, 1), NaiveTime::from_hms(1, 0, 15)),
+            NaiveDateTime::new(NaiveDate::from_ymd(2001, 1, 1), NaiveTime::from_hms(1, 0, 45)),
+        ];
+        let sorted_ts = dt.iter().map(|dt| dt.timestamp_nanos()).collect::<Vec<_>>();
+        // shuffle: original row 0 holds the latest timestamp, row 1 the earliest
+        let unsorted_ts = vec![sorted_ts[2], sorted_ts[0], sorted_ts[1]];
+
+        let window = Window::new(
+            Duration::from_seconds(30),
+            Duration::from_seconds(30),
+            Duration::from_seconds(0),
+        );
+
+        let gt = groupby_unsorted(window, &unsorted_ts, ClosedWindow::Left, TimeUnit::Nanoseconds).unwrap();
+
+        // row 1 (1:00:00) and row 2 (1:00:15) share the first window; row 0 (1:00:45) is alone in
+        // the second. every index refers to a position in `unsorted_ts`, not the sorted order.
+        let expected: GroupTuples = vec![(1, vec![1, 2]), (0, vec![0])];
         assert_eq!(gt, expected);
     }
+
+    #[test]
+    fn test_groupby_empty_time_is_empty_not_panic() {
+        let window = Window::new(
+            Duration::from_seconds(30),
+            Duration::from_seconds(30),
+            Duration::from_seconds(0),
+        );
+        let gt = groupby(window, &[], ClosedWindow::Left, TimeUnit::Nanoseconds).unwrap();
+        assert!(gt.is_empty());
+    }
+
+    #[test]
+    fn test_groupby_monthly_window() {
+        // a calendar (months) `every` used to make `estimate_overlapping_bounds` compute a
+        // `Vec::with_capacity` hint the size of the whole boundary span in nanoseconds, aborting
+        // the process; this exercises that path through the public `groupby` entry point rather
+        // than `get_overlapping_bounds_iter` directly.
+        let start = NaiveDateTime::new(NaiveDate::from_ymd(2001, 1, 1), NaiveTime::from_hms(0, 0, 0));
+        let ts = (0..365)
+            .map(|day| (start + chrono::Duration::days(day)).timestamp_nanos())
+            .collect::<Vec<_>>();
+
+        let window = Window::new(
+            Duration::from_months(1),
+            Duration::from_months(1),
+            Duration::from_nsecs(0),
+        );
+
+        let gt = groupby(window, &ts, ClosedWindow::Left, TimeUnit::Nanoseconds).unwrap();
+
+        // one group per calendar month touched by the year of daily timestamps
+        assert_eq!(gt.len(), 12);
+        assert_eq!(gt.iter().map(|(_, g)| g.len()).sum::<usize>(), 365);
+    }
 }
\ No newline at end of file
@@ -0,0 +1,12 @@
+mod bounds;
+mod calendar;
+mod duration;
+mod groupby;
+mod unit;
+mod window;
+
+pub use bounds::{Bounds, ClosedWindow};
+pub use duration::Duration;
+pub use groupby::{groupby, groupby_unsorted, GroupTuples};
+pub use unit::TimeUnit;
+pub use window::{Window, WindowError};
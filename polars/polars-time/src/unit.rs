@@ -0,0 +1,24 @@
+/// The resolution timestamps in a time column are stored at.
+///
+/// `Window`/`Duration` convert a column's raw `i64` values to nanoseconds internally and the
+/// results back to the column's unit. Fixed-length arithmetic does this in `i128`, so it cannot
+/// overflow; calendar (months) and timezone arithmetic converts in `i64`, saturating at
+/// `i64::MAX`/`MIN` for far-future/far-past timestamps stored as milliseconds or microseconds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum TimeUnit {
+    #[default]
+    Nanoseconds,
+    Microseconds,
+    Milliseconds,
+}
+
+impl TimeUnit {
+    /// how many nanoseconds make up a single unit of `self`
+    pub(crate) fn ns_per_unit(&self) -> i64 {
+        match self {
+            TimeUnit::Nanoseconds => 1,
+            TimeUnit::Microseconds => 1_000,
+            TimeUnit::Milliseconds => 1_000_000,
+        }
+    }
+}
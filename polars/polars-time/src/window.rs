@@ -0,0 +1,401 @@
+use crate::bounds::Bounds;
+use crate::duration::Duration;
+use crate::unit::TimeUnit;
+use chrono_tz::Tz;
+use std::fmt;
+
+/// Error returned when a [`Window`] cannot be iterated against a given [`TimeUnit`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WindowError {
+    /// `every` rounds to zero in the requested time unit, so the window would never advance.
+    EveryRoundsToZero,
+}
+
+impl fmt::Display for WindowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WindowError::EveryRoundsToZero => write!(
+                f,
+                "window `every` must not round to zero in the column's time unit; it would \
+                 never advance and grouping would never terminate"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WindowError {}
+
+/// Defines a set of possibly-overlapping windows used to group a sorted timestamp column.
+///
+/// A window repeats every `every`, has length `period`, and is shifted by `offset` relative to
+/// the start of each repetition. When `tz` is set, boundaries are truncated/stepped against
+/// local wall-clock time in that zone (handling DST gaps/overlaps) instead of UTC.
+#[derive(Clone, Copy, Debug)]
+pub struct Window {
+    pub(crate) every: Duration,
+    pub(crate) period: Duration,
+    pub(crate) offset: Duration,
+    pub(crate) tz: Option<Tz>,
+}
+
+impl Window {
+    pub fn new(every: Duration, period: Duration, offset: Duration) -> Self {
+        Window {
+            every,
+            period,
+            offset,
+            tz: None,
+        }
+    }
+
+    /// Truncate/step window boundaries against local wall-clock time in `tz` rather than UTC.
+    pub fn with_timezone(mut self, tz: Option<Tz>) -> Self {
+        self.tz = tz;
+        self
+    }
+
+    /// A cheap estimate of the number of windows that overlap `boundary`, used to
+    /// pre-allocate the groupby output; it is not guaranteed to be an upper bound. `tu` is the unit `boundary` (and the column it was
+    /// built from) is expressed in.
+    ///
+    /// Uses [`Duration::estimated_duration`] rather than [`Duration::duration`]: the latter is
+    /// zero for a calendar-only `every`, which would turn this into a `boundary`-sized capacity
+    /// hint instead of a window count.
+    pub fn estimate_overlapping_bounds(&self, boundary: Bounds, tu: TimeUnit) -> usize {
+        (boundary.duration() / self.every.estimated_duration(tu).max(1)) as usize + 1
+    }
+
+    /// Iterate the bounds of the windows over `boundary`, a range expressed in `tu`. Whether a
+    /// window's `start`/`stop` are members is decided by the [`ClosedWindow`] passed to
+    /// [`Bounds::is_member`].
+    ///
+    /// The first window starts at `boundary.start` truncated to `every`, shifted by `offset`,
+    /// and windows are emitted until one starts at or past `boundary.stop`. A window that starts
+    /// before that first window is not emitted, even if it overlaps `boundary`.
+    ///
+    /// [`ClosedWindow`]: crate::bounds::ClosedWindow
+    ///
+    /// When `every` has no calendar component, the lower boundary of each window is found by
+    /// scaling `every` by the window's index and adding it to the first window's start, rather
+    /// than by repeatedly adding `every` to the previous window — see [`Duration::scaled`] — so
+    /// that a fixed component that isn't an exact multiple of `tu.ns_per_unit()` doesn't drift
+    /// over many windows. When `every` has a calendar (months) component, each window's start is
+    /// instead found by repeatedly adding `every` to the previous window's start, since
+    /// `step_months` clamps the day-of-month against whatever month the previous step landed on
+    /// and jumping straight to the nth window with a single scaled add would clamp against the
+    /// first window's day every time instead.
+    ///
+    /// Returns [`WindowError::EveryRoundsToZero`] if `every` rounds to zero in `tu`, since the
+    /// iterator would then never advance past its first window.
+    pub fn get_overlapping_bounds_iter(
+        &self,
+        boundary: Bounds,
+        tu: TimeUnit,
+    ) -> Result<BoundsIter, WindowError> {
+        BoundsIter::new(*self, boundary, tu)
+    }
+}
+
+#[derive(Debug)]
+pub struct BoundsIter {
+    window: Window,
+    tu: TimeUnit,
+    // the first window's start; every later fixed-only window is found by scaling `every` by `n`
+    // and adding it to this once, rather than by repeatedly adding `every` to the previous
+    // window's start — see `Duration::scaled` for why that matters
+    origin: i64,
+    // how many `every`-steps from `origin` the window about to be emitted is, only meaningful
+    // when `every` has no calendar component (see `current` otherwise)
+    n: i64,
+    // the start of the window about to be emitted, kept up to date by repeated `Duration::add`
+    // calls whenever `every` has a calendar (months) component. Unlike the fixed case,
+    // `step_months` clamps the day against whatever month the *previous* step landed on, so
+    // jumping straight to the nth window via a single scaled add (as the fixed case does) would
+    // clamp against the origin's day every time instead and silently drift once any step clamps.
+    current: i64,
+    boundary: Bounds,
+}
+
+impl BoundsIter {
+    fn new(window: Window, boundary: Bounds, tu: TimeUnit) -> Result<Self, WindowError> {
+        if window.every.is_zero_in(tu) {
+            return Err(WindowError::EveryRoundsToZero);
+        }
+        let start = match window.tz {
+            Some(tz) => window.every.truncate_tz(boundary.start, tz, tu),
+            None => window.every.truncate(boundary.start, tu),
+        };
+        let origin = match window.tz {
+            Some(tz) => window.offset.add_tz(start, tz, tu),
+            None => window.offset.add(start, tu),
+        };
+        Ok(BoundsIter {
+            window,
+            tu,
+            origin,
+            n: 0,
+            current: origin,
+            boundary,
+        })
+    }
+}
+
+impl Iterator for BoundsIter {
+    type Item = Bounds;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let has_calendar_component = self.window.every.months() != 0;
+        let start = if has_calendar_component {
+            self.current
+        } else {
+            let every_n = self.window.every.scaled(self.n);
+            match self.window.tz {
+                Some(tz) => every_n.add_tz(self.origin, tz, self.tu),
+                None => every_n.add(self.origin, self.tu),
+            }
+        };
+        if start >= self.boundary.stop {
+            return None;
+        }
+        let stop = match self.window.tz {
+            Some(tz) => self.window.period.add_tz(start, tz, self.tu),
+            None => self.window.period.add(start, self.tu),
+        };
+        if has_calendar_component {
+            self.current = match self.window.tz {
+                Some(tz) => self.window.every.add_tz(self.current, tz, self.tu),
+                None => self.window.every.add(self.current, self.tu),
+            };
+        }
+        self.n += 1;
+        Some(Bounds::new(start, stop))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::calendar::datetime_to_timestamp_ns;
+    use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+
+    #[test]
+    fn test_monthly_window_bounds() {
+        let window = Window::new(
+            Duration::from_months(1),
+            Duration::from_months(1),
+            Duration::from_nsecs(0),
+        );
+        let start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 1, 15),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let stop = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 3, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let boundary = Bounds::new(start, stop);
+
+        let bounds = window
+            .get_overlapping_bounds_iter(boundary, TimeUnit::Nanoseconds)
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        let jan_start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 1, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let feb_start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 2, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let mar_start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 3, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+
+        assert_eq!(bounds[0].start, jan_start);
+        assert_eq!(bounds[0].stop, feb_start);
+        assert_eq!(bounds[1].start, feb_start);
+        assert_eq!(bounds[1].stop, mar_start);
+    }
+
+    #[test]
+    fn test_daily_window_across_dst_spring_forward() {
+        // 2021-03-14 is the day America/New_York springs forward, so the local day only spans
+        // 23 hours of absolute time; the window should still be anchored to local midnight.
+        let tz: Tz = "America/New_York".parse().unwrap();
+        let window = Window::new(
+            Duration::from_days(1),
+            Duration::from_days(1),
+            Duration::from_nsecs(0),
+        )
+        .with_timezone(Some(tz));
+
+        let start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2021, 3, 14),
+            NaiveTime::from_hms(12, 0, 0),
+        )) + 4 * 60 * 60 * 1_000_000_000; // noon EDT, expressed as a UTC instant
+        let boundary = Bounds::new(start, start + 1);
+
+        let bi = window
+            .get_overlapping_bounds_iter(boundary, TimeUnit::Nanoseconds)
+            .unwrap()
+            .next()
+            .unwrap();
+        let expected_start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2021, 3, 14),
+            NaiveTime::from_hms(0, 0, 0),
+        )) + 5 * 60 * 60 * 1_000_000_000; // local midnight EST, as a UTC instant
+        let expected_stop = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2021, 3, 15),
+            NaiveTime::from_hms(0, 0, 0),
+        )) + 4 * 60 * 60 * 1_000_000_000; // next local midnight, now EDT, as a UTC instant
+
+        assert_eq!(bi.start, expected_start);
+        assert_eq!(bi.stop, expected_stop);
+    }
+
+    #[test]
+    fn test_same_window_regardless_of_time_unit() {
+        let window = Window::new(
+            Duration::from_seconds(30),
+            Duration::from_seconds(30),
+            Duration::from_nsecs(0),
+        );
+        let start_ns = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 1, 1),
+            NaiveTime::from_hms(1, 0, 0),
+        ));
+        let boundary_ns = Bounds::new(start_ns, start_ns + 60_000_000_000);
+        let boundary_ms = Bounds::new(start_ns / 1_000_000, (start_ns + 60_000_000_000) / 1_000_000);
+
+        let bounds_ns = window
+            .get_overlapping_bounds_iter(boundary_ns, TimeUnit::Nanoseconds)
+            .unwrap()
+            .collect::<Vec<_>>();
+        let bounds_ms = window
+            .get_overlapping_bounds_iter(boundary_ms, TimeUnit::Milliseconds)
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        assert_eq!(bounds_ns.len(), bounds_ms.len());
+        for (ns, ms) in bounds_ns.iter().zip(bounds_ms.iter()) {
+            assert_eq!(ns.start / 1_000_000, ms.start);
+            assert_eq!(ns.stop / 1_000_000, ms.stop);
+        }
+    }
+
+    #[test]
+    fn test_overlapping_bounds_near_i64_max_does_not_panic() {
+        // a fixed window whose cursor advances close to i64::MAX should saturate, not overflow
+        let window = Window::new(
+            Duration::from_seconds(30),
+            Duration::from_seconds(30),
+            Duration::from_nsecs(0),
+        );
+        let boundary = Bounds::new(i64::MAX - 100_000, i64::MAX);
+
+        let bounds = window
+            .get_overlapping_bounds_iter(boundary, TimeUnit::Milliseconds)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert!(!bounds.is_empty());
+    }
+
+    #[test]
+    fn test_sub_resolution_every_errors_instead_of_hanging() {
+        // a 500ns `every` against a millisecond-resolution column rounds to zero windows' worth
+        // of advance per step, which would otherwise make `BoundsIter::next` spin forever
+        let window = Window::new(
+            Duration::from_nsecs(500),
+            Duration::from_nsecs(500),
+            Duration::from_nsecs(0),
+        );
+        let boundary = Bounds::new(0, 1_000_000);
+        assert_eq!(
+            window
+                .get_overlapping_bounds_iter(boundary, TimeUnit::Milliseconds)
+                .unwrap_err(),
+            WindowError::EveryRoundsToZero
+        );
+    }
+
+    #[test]
+    fn test_mixed_calendar_and_fixed_duration_matches_iterative_add() {
+        // "1 month plus 2 days" is the motivating mixed duration from the calendar-window
+        // request; jumping to the nth window via a single scaled add clamps the day-of-month
+        // against the very first window's day on every step instead of the previous window's,
+        // silently drifting once any step clamps. Compare many windows' worth of output against
+        // the same duration added iteratively, which is unambiguously correct since each step
+        // reasons from a concrete timestamp rather than scaling from the origin.
+        let every = Duration::new(1, Duration::from_days(2).nsecs());
+        let window = Window::new(every, every, Duration::from_nsecs(0));
+        let start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 1, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let stop = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2003, 1, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let boundary = Bounds::new(start, stop);
+
+        let bounds = window
+            .get_overlapping_bounds_iter(boundary, TimeUnit::Nanoseconds)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert!(bounds.len() > 12);
+
+        let mut expected_start = start;
+        for b in &bounds {
+            assert_eq!(b.start, expected_start);
+            expected_start = every.add(expected_start, TimeUnit::Nanoseconds);
+        }
+    }
+
+    #[test]
+    fn test_calendar_offset_day_clamp_is_iterative_not_origin_relative() {
+        // the origin truncates to Jan 1, then a 30 day offset lands it on Jan 31 — a
+        // day-of-month that clamps in shorter months. A monthly `every` must keep clamping
+        // against whatever day the *previous* window landed on, not jump back to the origin's
+        // day-31 every time: Jan31 -> Feb28 (clamped) -> Mar28 (not Mar31, which is what
+        // re-clamping day 31 against March would wrongly give).
+        let window = Window::new(
+            Duration::from_months(1),
+            Duration::from_months(1),
+            Duration::from_days(30),
+        );
+        let start = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 1, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let stop = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 4, 1),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let boundary = Bounds::new(start, stop);
+
+        let bounds = window
+            .get_overlapping_bounds_iter(boundary, TimeUnit::Nanoseconds)
+            .unwrap()
+            .collect::<Vec<_>>();
+
+        let jan_31 = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 1, 31),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let feb_28 = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 2, 28),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+        let mar_28 = datetime_to_timestamp_ns(NaiveDateTime::new(
+            NaiveDate::from_ymd(2001, 3, 28),
+            NaiveTime::from_hms(0, 0, 0),
+        ));
+
+        assert_eq!(bounds.len(), 3);
+        assert_eq!(bounds[0].start, jan_31);
+        assert_eq!(bounds[1].start, feb_28);
+        assert_eq!(bounds[2].start, mar_28);
+    }
+}